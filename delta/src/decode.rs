@@ -0,0 +1,104 @@
+use crate::errors::DeltaError;
+use crate::utils::decode_size_varint;
+
+/// Decodes a Git-format delta (as produced by [`crate::encode::DeltaDiff::encode`]) against
+/// `base`, returning the reconstructed target bytes.
+pub fn delta_decode(delta: &[u8], base: &[u8]) -> Result<Vec<u8>, DeltaError> {
+    let (_base_size, mut pos) = decode_size_varint(delta)?;
+    let (target_size, consumed) = decode_size_varint(&delta[pos..])?;
+    pos += consumed;
+
+    let mut out = Vec::with_capacity(target_size);
+    while pos < delta.len() {
+        let cmd = delta[pos];
+        pos += 1;
+
+        if cmd & 0x80 != 0 {
+            let (offset, size) = decode_copy(delta, &mut pos, cmd)?;
+            let end = offset.checked_add(size).ok_or(DeltaError::UnexpectedEof)?;
+            let slice = base
+                .get(offset..end)
+                .ok_or(DeltaError::CopyOutOfBounds {
+                    offset,
+                    size,
+                    base_len: base.len(),
+                })?;
+            out.extend_from_slice(slice);
+        } else if cmd != 0 {
+            let len = cmd as usize;
+            let end = pos + len;
+            let slice = delta.get(pos..end).ok_or(DeltaError::UnexpectedEof)?;
+            out.extend_from_slice(slice);
+            pos = end;
+        } else {
+            // 0 is reserved in the Git delta format and never produced by the encoder.
+            return Err(DeltaError::UnexpectedEof);
+        }
+    }
+
+    Ok(out)
+}
+
+/// Reads a copy instruction's offset and size fields, as indicated by `cmd`'s low 7 bits: bits
+/// 0-3 mark which of up to four offset bytes follow, bits 4-6 mark which of up to three size
+/// bytes follow, and any byte not present is treated as `0`.
+fn decode_copy(delta: &[u8], pos: &mut usize, cmd: u8) -> Result<(usize, usize), DeltaError> {
+    let mut offset: u32 = 0;
+    let mut size: u32 = 0;
+
+    for i in 0..4 {
+        if cmd & (1 << i) != 0 {
+            let byte = *delta.get(*pos).ok_or(DeltaError::UnexpectedEof)?;
+            offset |= (byte as u32) << (8 * i);
+            *pos += 1;
+        }
+    }
+    for i in 0..3 {
+        if cmd & (1 << (4 + i)) != 0 {
+            let byte = *delta.get(*pos).ok_or(DeltaError::UnexpectedEof)?;
+            size |= (byte as u32) << (8 * i);
+            *pos += 1;
+        }
+    }
+    if size == 0 {
+        size = 0x10000;
+    }
+    Ok((offset as usize, size as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encode::DeltaDiff;
+
+    #[test]
+    fn round_trips_overlapping_content() {
+        let old = b"the quick brown fox jumps over the lazy dog".repeat(4);
+        let mut new = old.clone();
+        new.truncate(new.len() - 10);
+        new.extend_from_slice(b" and then some brand new tail content");
+
+        let delta = DeltaDiff::new(&old, &new).encode();
+        let decoded = delta_decode(&delta, &old).unwrap();
+        assert_eq!(decoded, new);
+    }
+
+    #[test]
+    fn round_trips_disjoint_content() {
+        let old = vec![b'a'; 80];
+        let new = vec![b'b'; 80];
+
+        let delta = DeltaDiff::new(&old, &new).encode();
+        let decoded = delta_decode(&delta, &old).unwrap();
+        assert_eq!(decoded, new);
+    }
+
+    #[test]
+    fn copy_past_base_end_is_an_error() {
+        // A hand-built delta: base size 1, target size 1, copy instruction requesting 4 bytes
+        // at offset 0 from a 1-byte base.
+        let delta = vec![1, 1, 0x81 | 0x10, 0, 4];
+        let err = delta_decode(&delta, &[0u8]).unwrap_err();
+        assert!(matches!(err, DeltaError::CopyOutOfBounds { .. }));
+    }
+}
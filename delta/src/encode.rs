@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use crate::utils::encode_size_varint;
+
+/// Maximum number of bytes a single copy instruction can address. Per the Git delta format, a
+/// size field of `0` is the sentinel for exactly this value (it can't represent itself, since
+/// `0` would otherwise mean "copy nothing").
+const MAX_COPY_LEN: usize = 0x10000;
+/// Maximum literal run a single insert instruction can carry: the top bit of the command byte
+/// must stay clear to distinguish it from a copy instruction, so `0x7f` is the largest length
+/// an insert byte can encode.
+const MAX_INSERT_LEN: usize = 0x7f;
+/// Block size used to index `old_data` for matching. Matches shorter than this aren't worth a
+/// copy instruction's overhead (offset + size bytes) and are left as literals instead.
+const BLOCK_SIZE: usize = 16;
+
+/// Computes a Git-format delta of `new_data` against `old_data`.
+pub struct DeltaDiff<'a> {
+    old_data: &'a [u8],
+    new_data: &'a [u8],
+}
+
+impl<'a> DeltaDiff<'a> {
+    pub fn new(old_data: &'a [u8], new_data: &'a [u8]) -> Self {
+        DeltaDiff { old_data, new_data }
+    }
+
+    /// Heuristic "savings rate": the fraction of `new_data` that a delta against `old_data`
+    /// could express as copies rather than literal inserts. Callers use this to decide whether
+    /// delta-encoding `new_data` is worthwhile versus storing it whole.
+    pub fn get_ssam_rate(&self) -> f64 {
+        if self.new_data.is_empty() {
+            return 0.0;
+        }
+
+        let index = self.index_old();
+        let mut copied = 0usize;
+        let mut pos = 0usize;
+        while pos < self.new_data.len() {
+            match self.longest_match(&index, pos) {
+                Some((_, len)) => {
+                    copied += len;
+                    pos += len;
+                }
+                None => pos += 1,
+            }
+        }
+        copied as f64 / self.new_data.len() as f64
+    }
+
+    /// Encodes `new_data` as a Git-format delta against `old_data`: the base and target sizes
+    /// as varints, followed by a stream of copy/insert instructions.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend(encode_size_varint(self.old_data.len()));
+        out.extend(encode_size_varint(self.new_data.len()));
+
+        let index = self.index_old();
+        let mut literal = Vec::new();
+        let mut pos = 0;
+        while pos < self.new_data.len() {
+            match self.longest_match(&index, pos) {
+                Some((offset, len)) => {
+                    flush_literal(&mut out, &mut literal);
+                    encode_copy_run(&mut out, offset, len);
+                    pos += len;
+                }
+                None => {
+                    literal.push(self.new_data[pos]);
+                    pos += 1;
+                }
+            }
+        }
+        flush_literal(&mut out, &mut literal);
+        out
+    }
+
+    /// Indexes every `BLOCK_SIZE`-byte window of `old_data` by its bytes, keeping the earliest
+    /// offset for each distinct window.
+    fn index_old(&self) -> HashMap<&'a [u8], usize> {
+        let mut index = HashMap::new();
+        if self.old_data.len() < BLOCK_SIZE {
+            return index;
+        }
+        for offset in 0..=self.old_data.len() - BLOCK_SIZE {
+            index
+                .entry(&self.old_data[offset..offset + BLOCK_SIZE])
+                .or_insert(offset);
+        }
+        index
+    }
+
+    /// Looks up the block starting at `new_data[pos..]` in `index` and, on a hit, extends the
+    /// match forward as far as the two buffers keep agreeing.
+    fn longest_match(&self, index: &HashMap<&'a [u8], usize>, pos: usize) -> Option<(usize, usize)> {
+        if pos + BLOCK_SIZE > self.new_data.len() {
+            return None;
+        }
+        let key = &self.new_data[pos..pos + BLOCK_SIZE];
+        let &offset = index.get(key)?;
+
+        let mut len = BLOCK_SIZE;
+        while offset + len < self.old_data.len()
+            && pos + len < self.new_data.len()
+            && self.old_data[offset + len] == self.new_data[pos + len]
+        {
+            len += 1;
+        }
+        Some((offset, len))
+    }
+}
+
+fn flush_literal(out: &mut Vec<u8>, literal: &mut Vec<u8>) {
+    for chunk in literal.chunks(MAX_INSERT_LEN) {
+        out.push(chunk.len() as u8);
+        out.extend_from_slice(chunk);
+    }
+    literal.clear();
+}
+
+/// Emits one or more copy instructions covering `[offset, offset + len)`, splitting at
+/// `MAX_COPY_LEN` boundaries since a single instruction can't address more than that.
+fn encode_copy_run(out: &mut Vec<u8>, mut offset: usize, mut len: usize) {
+    while len > 0 {
+        let take = len.min(MAX_COPY_LEN);
+        out.extend(encode_copy_instruction(offset, take));
+        offset += take;
+        len -= take;
+    }
+}
+
+/// Encodes a single copy instruction: a command byte with the high bit set, whose low 7 bits
+/// are a bitmap of which offset/size bytes follow (zero bytes are omitted and assumed `0` on
+/// decode).
+fn encode_copy_instruction(offset: usize, len: usize) -> Vec<u8> {
+    let offset_bytes = (offset as u32).to_le_bytes();
+    let size_field = if len == MAX_COPY_LEN { 0 } else { len as u32 };
+    let size_bytes = size_field.to_le_bytes();
+
+    let mut cmd = 0x80u8;
+    let mut body = Vec::new();
+    for (i, &byte) in offset_bytes.iter().enumerate() {
+        if byte != 0 {
+            cmd |= 1 << i;
+            body.push(byte);
+        }
+    }
+    for (i, &byte) in size_bytes[..3].iter().enumerate() {
+        if byte != 0 {
+            cmd |= 1 << (4 + i);
+            body.push(byte);
+        }
+    }
+
+    let mut out = vec![cmd];
+    out.append(&mut body);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ssam_rate_is_zero_for_disjoint_content() {
+        let old = vec![b'a'; BLOCK_SIZE * 4];
+        let new = vec![b'b'; BLOCK_SIZE * 4];
+        assert_eq!(DeltaDiff::new(&old, &new).get_ssam_rate(), 0.0);
+    }
+
+    #[test]
+    fn ssam_rate_is_one_for_identical_content() {
+        let data = vec![b'a'; BLOCK_SIZE * 4];
+        assert_eq!(DeltaDiff::new(&data, &data).get_ssam_rate(), 1.0);
+    }
+
+    #[test]
+    fn encode_emits_size_header() {
+        let old = b"the quick brown fox".to_vec();
+        let new = b"the quick brown fox jumps".to_vec();
+        let delta = DeltaDiff::new(&old, &new).encode();
+        assert_eq!(delta[0] as usize, old.len());
+        assert_eq!(delta[1] as usize, new.len());
+    }
+}
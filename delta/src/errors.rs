@@ -0,0 +1,37 @@
+use std::fmt;
+
+/// Errors that can occur while decoding a Git-format delta.
+#[derive(Debug)]
+pub enum DeltaError {
+    /// The delta stream ended before an instruction could be fully read, or held the reserved
+    /// `0` command byte.
+    UnexpectedEof,
+    /// A copy instruction's `[offset, offset + size)` range ran past the end of the base
+    /// object.
+    CopyOutOfBounds {
+        offset: usize,
+        size: usize,
+        base_len: usize,
+    },
+}
+
+impl fmt::Display for DeltaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeltaError::UnexpectedEof => write!(f, "delta stream is truncated or malformed"),
+            DeltaError::CopyOutOfBounds {
+                offset,
+                size,
+                base_len,
+            } => write!(
+                f,
+                "copy instruction [{}, {}) is out of bounds for a base object of size {}",
+                offset,
+                offset + size,
+                base_len
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DeltaError {}
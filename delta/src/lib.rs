@@ -5,9 +5,8 @@ mod encode;
 mod errors;
 mod utils;
 
-
-
 pub use decode::delta_decode;
+pub use errors::DeltaError;
 pub fn delta_encode_rate(old_data: & [u8], new_data: & [u8]) -> f64{
     let differ = DeltaDiff::new(old_data, new_data);
     differ.get_ssam_rate()
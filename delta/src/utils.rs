@@ -0,0 +1,47 @@
+use crate::errors::DeltaError;
+
+/// Encodes `n` as a Git-style little-endian base-128 varint: each byte carries 7 bits of value,
+/// with the high bit set on every byte but the last to signal "more bytes follow".
+pub fn encode_size_varint(mut n: usize) -> Vec<u8> {
+    let mut out = Vec::new();
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            out.push(byte);
+            break;
+        }
+        out.push(byte | 0x80);
+    }
+    out
+}
+
+/// Decodes a varint written by [`encode_size_varint`] from the start of `data`, returning the
+/// value and the number of bytes consumed.
+pub fn decode_size_varint(data: &[u8]) -> Result<(usize, usize), DeltaError> {
+    let mut value = 0usize;
+    let mut shift = 0u32;
+    for (consumed, &byte) in data.iter().enumerate() {
+        value |= ((byte & 0x7f) as usize) << shift;
+        if byte & 0x80 == 0 {
+            return Ok((value, consumed + 1));
+        }
+        shift += 7;
+    }
+    Err(DeltaError::UnexpectedEof)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn varint_round_trips() {
+        for n in [0usize, 1, 127, 128, 300, 0x10000, 0xffff_ffff] {
+            let encoded = encode_size_varint(n);
+            let (decoded, consumed) = decode_size_varint(&encoded).unwrap();
+            assert_eq!(decoded, n);
+            assert_eq!(consumed, encoded.len());
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! This module implements the `hash-object` command for the Libra CLI.
+//!
+//! Mirrors `git hash-object`: computes the `Blob` object for a file's content and, with `-w`,
+//! persists it - which for a chunked `Blob` means writing each entry from
+//! [`Blob::chunk_refs`] as its own content-addressed object under `.libra/objects`, so chunks
+//! shared with other files or earlier revisions are written to disk exactly once.
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use venus::internal::object::blob::Blob;
+
+use crate::internal::object_store;
+use crate::utils::util::ROOT_DIR;
+
+#[derive(Parser, Debug)]
+pub struct HashObjectArgs {
+    /// Path of the file to hash
+    pub file: PathBuf,
+
+    /// Persist the resulting chunks to the object database
+    #[clap(short = 'w', long)]
+    pub write: bool,
+}
+
+/// Execute the hash-object function
+pub async fn execute(args: HashObjectArgs) {
+    hash_object(args).await.unwrap();
+}
+
+/// Hash `args.file`'s content as a `Blob`, writing its chunks to `.libra/objects` if `-w` was
+/// passed, and print the blob's id.
+pub async fn hash_object(args: HashObjectArgs) -> io::Result<()> {
+    let content = fs::read_to_string(&args.file)?;
+    let blob = Blob::from_content(&content);
+
+    if args.write {
+        let objects_dir = std::env::current_dir()?.join(ROOT_DIR).join("objects");
+        let total_chunks = blob.chunk_refs().count();
+        let written = object_store::save_blob(&objects_dir, &blob)?;
+        println!(
+            "{} ({} chunk(s) written, {} already present)",
+            blob.id,
+            written,
+            total_chunks - written
+        );
+    } else {
+        println!("{}", blob.id);
+    }
+
+    Ok(())
+}
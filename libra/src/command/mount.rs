@@ -0,0 +1,54 @@
+//! This module implements the `mount` command for the Libra CLI.
+//!
+//! `mount` exposes a ref as a read-only [FUSE](https://docs.kernel.org/filesystems/fuse.html)
+//! filesystem, so a historical revision can be browsed with ordinary tools (`ls`, `cat`, an
+//! editor, ...) without checking it out into the working directory.
+//!
+//! Only available when Libra is built with the `fuse` feature.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::internal::db;
+use crate::internal::fuse::{LibraFs, ObjectRootNodes, RootNodes};
+
+#[derive(Parser, Debug)]
+pub struct MountArgs {
+    /// The commit, branch, or tag to mount
+    pub reference: String,
+
+    /// Directory to mount the filesystem at
+    pub mountpoint: PathBuf,
+}
+
+/// Execute the mount function
+pub async fn execute(args: MountArgs) {
+    mount(args).await.unwrap();
+}
+
+/// Mount `args.reference` read-only at `args.mountpoint` and block until it is unmounted.
+pub async fn mount(args: MountArgs) -> std::io::Result<()> {
+    let storage = db::ObjectStorage::open_current_repo().await?;
+    let roots = ObjectRootNodes::new(storage);
+
+    let root_tree = roots.root_tree(&args.reference).ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("reference '{}' does not resolve to a commit", args.reference),
+        )
+    })?;
+
+    println!(
+        "Mounting '{}' at {} (read-only, Ctrl+C to unmount)",
+        args.reference,
+        args.mountpoint.display()
+    );
+
+    let fs = LibraFs::new(roots, root_tree);
+    let options = [
+        fuser::MountOption::RO,
+        fuser::MountOption::FSName("libra".to_string()),
+    ];
+    fuser::mount2(fs, &args.mountpoint, &options)
+}
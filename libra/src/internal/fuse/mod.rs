@@ -0,0 +1,339 @@
+//! Read-only FUSE view over the Libra object database.
+//!
+//! Unlike a checkout, mounting a ref does not materialize any files on disk: `lookup`/`readdir`
+//! walk `Tree` objects on demand and `read` resolves `Blob` contents lazily via
+//! [`ObjectTrait::to_data`], so browsing an old revision costs nothing until its files are
+//! actually touched.
+
+mod root;
+
+pub use root::{ObjectRootNodes, RootNodes};
+
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use fuser::{FileAttr, FileType, Filesystem, ReplyAttr, ReplyData, ReplyDirectory, ReplyEntry, Request};
+
+use venus::hash::SHA1;
+use venus::internal::object::tree::{Tree, TreeItemMode};
+use venus::internal::object::ObjectTrait;
+
+const TTL: Duration = Duration::from_secs(1);
+
+/// A single node in the mounted tree: either a directory (another `Tree`) or a file (a `Blob`).
+#[derive(Clone)]
+enum Node {
+    Dir(Tree),
+    File(SHA1),
+}
+
+/// Read-only [`Filesystem`] backed by a [`RootNodes`] implementation.
+///
+/// The FUSE layer never touches the object *database* directly; it is handed the top-level tree
+/// of the mounted ref and then walks `Tree`/`Blob` objects that `roots` hands back, so it has no
+/// dependency on the storage crate.
+pub struct LibraFs<R: RootNodes> {
+    roots: R,
+    /// inode -> resolved node, populated lazily as `lookup`/`readdir` walk the tree.
+    inodes: Mutex<HashMap<u64, Node>>,
+    /// (parent inode, entry name) -> the inode already allocated for that entry, so repeatedly
+    /// listing or stat-ing the same directory doesn't grow `inodes` without bound.
+    children: Mutex<HashMap<(u64, String), u64>>,
+    /// Decompressed blob bytes, cached by content hash so re-opening the same blob under a
+    /// different path (or after a `readdir`) doesn't re-hit the object database.
+    blob_cache: Mutex<HashMap<SHA1, Vec<u8>>>,
+    next_inode: Mutex<u64>,
+}
+
+const FUSE_ROOT_INODE: u64 = 1;
+
+impl<R: RootNodes> LibraFs<R> {
+    pub fn new(roots: R, root_tree: Tree) -> Self {
+        let mut inodes = HashMap::new();
+        inodes.insert(FUSE_ROOT_INODE, Node::Dir(root_tree));
+        LibraFs {
+            roots,
+            inodes: Mutex::new(inodes),
+            children: Mutex::new(HashMap::new()),
+            blob_cache: Mutex::new(HashMap::new()),
+            next_inode: Mutex::new(FUSE_ROOT_INODE + 1),
+        }
+    }
+
+    fn alloc_inode(&self, node: Node) -> u64 {
+        let mut next = self.next_inode.lock().unwrap();
+        let inode = *next;
+        *next += 1;
+        self.inodes.lock().unwrap().insert(inode, node);
+        inode
+    }
+
+    /// Returns the inode for the entry named `name` under `parent`, allocating (and caching)
+    /// one on first sight and reusing it on every later `lookup`/`readdir` of the same entry.
+    fn child_inode(&self, parent: u64, name: &str, mode: TreeItemMode, id: SHA1) -> Option<u64> {
+        let key = (parent, name.to_string());
+        if let Some(&inode) = self.children.lock().unwrap().get(&key) {
+            return Some(inode);
+        }
+
+        let node = match mode {
+            TreeItemMode::Tree => Node::Dir(self.roots.load_tree(id)?),
+            _ => Node::File(id),
+        };
+        let inode = self.alloc_inode(node);
+        self.children.lock().unwrap().insert(key, inode);
+        Some(inode)
+    }
+
+    fn dir_attr(ino: u64) -> FileAttr {
+        Self::attr(ino, FileType::Directory, 0)
+    }
+
+    fn file_attr(ino: u64, size: usize) -> FileAttr {
+        Self::attr(ino, FileType::RegularFile, size as u64)
+    }
+
+    fn attr(ino: u64, kind: FileType, size: u64) -> FileAttr {
+        let now = std::time::SystemTime::now();
+        FileAttr {
+            ino,
+            size,
+            blocks: 0,
+            atime: now,
+            mtime: now,
+            ctime: now,
+            crtime: now,
+            kind,
+            perm: if kind == FileType::Directory { 0o555 } else { 0o444 },
+            nlink: 1,
+            uid: 0,
+            gid: 0,
+            rdev: 0,
+            blksize: 512,
+            flags: 0,
+        }
+    }
+
+    /// Resolve (and cache) the decompressed bytes for a blob id.
+    fn blob_data(&self, id: SHA1) -> Option<Vec<u8>> {
+        if let Some(data) = self.blob_cache.lock().unwrap().get(&id) {
+            return Some(data.clone());
+        }
+        let blob = self.roots.load_blob(id)?;
+        let data = blob.to_data().ok()?;
+        self.blob_cache.lock().unwrap().insert(id, data.clone());
+        Some(data)
+    }
+}
+
+impl<R: RootNodes> Filesystem for LibraFs<R> {
+    fn lookup(&mut self, _req: &Request, parent: u64, name: &OsStr, reply: ReplyEntry) {
+        let parent_tree = match self.inodes.lock().unwrap().get(&parent) {
+            Some(Node::Dir(tree)) => tree.clone(),
+            _ => return reply.error(libc::ENOENT),
+        };
+        let Some(name) = name.to_str() else {
+            return reply.error(libc::ENOENT);
+        };
+        let Some(item) = parent_tree.tree_items.iter().find(|i| i.name == name) else {
+            return reply.error(libc::ENOENT);
+        };
+        let Some(inode) = self.child_inode(parent, name, item.mode, item.id) else {
+            return reply.error(libc::EIO);
+        };
+
+        match item.mode {
+            TreeItemMode::Tree => reply.entry(&TTL, &Self::dir_attr(inode), 0),
+            _ => {
+                let Some(size) = self.blob_data(item.id).map(|d| d.len()) else {
+                    return reply.error(libc::EIO);
+                };
+                reply.entry(&TTL, &Self::file_attr(inode, size), 0);
+            }
+        }
+    }
+
+    fn getattr(&mut self, _req: &Request, ino: u64, reply: ReplyAttr) {
+        // Fetch the node as an owned value *before* matching on it: matching directly on the
+        // `MutexGuard` returned by `lock()` keeps that guard alive for every arm (Rust extends
+        // temporaries borrowed by a match scrutinee across the whole match), so locking
+        // `inodes` again inside an arm - as the `Node::File` case used to, to read the blob -
+        // would deadlock against itself.
+        let node = self.inodes.lock().unwrap().get(&ino).cloned();
+        match node {
+            Some(Node::Dir(_)) => reply.attr(&TTL, &Self::dir_attr(ino)),
+            Some(Node::File(id)) => match self.blob_data(id) {
+                Some(data) => reply.attr(&TTL, &Self::file_attr(ino, data.len())),
+                None => reply.error(libc::EIO),
+            },
+            None => reply.error(libc::ENOENT),
+        }
+    }
+
+    fn read(
+        &mut self,
+        _req: &Request,
+        ino: u64,
+        _fh: u64,
+        offset: i64,
+        size: u32,
+        _flags: i32,
+        _lock_owner: Option<u64>,
+        reply: ReplyData,
+    ) {
+        let id = match self.inodes.lock().unwrap().get(&ino) {
+            Some(Node::File(id)) => *id,
+            _ => return reply.error(libc::ENOENT),
+        };
+        let Some(data) = self.blob_data(id) else {
+            return reply.error(libc::EIO);
+        };
+        let offset = offset as usize;
+        if offset >= data.len() {
+            return reply.data(&[]);
+        }
+        let end = (offset + size as usize).min(data.len());
+        reply.data(&data[offset..end]);
+    }
+
+    fn readdir(&mut self, _req: &Request, ino: u64, _fh: u64, offset: i64, mut reply: ReplyDirectory) {
+        let tree = match self.inodes.lock().unwrap().get(&ino) {
+            Some(Node::Dir(tree)) => tree.clone(),
+            _ => return reply.error(libc::ENOENT),
+        };
+
+        let mut entries = vec![(ino, FileType::Directory, ".".to_string()), (ino, FileType::Directory, "..".to_string())];
+        for item in &tree.tree_items {
+            let Some(child_ino) = self.child_inode(ino, &item.name, item.mode, item.id) else {
+                return reply.error(libc::EIO);
+            };
+            let kind = match item.mode {
+                TreeItemMode::Tree => FileType::Directory,
+                _ => FileType::RegularFile,
+            };
+            entries.push((child_ino, kind, item.name.clone()));
+        }
+
+        for (i, (ino, kind, name)) in entries.into_iter().enumerate().skip(offset as usize) {
+            if reply.add(ino, (i + 1) as i64, kind, name) {
+                break;
+            }
+        }
+        reply.ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use venus::internal::object::blob::Blob;
+    use venus::internal::object::tree::TreeItem;
+    use venus::internal::object::types::ObjectType;
+
+    use super::*;
+
+    /// A [`RootNodes`] stub over a couple of fixed trees/blobs, counting how many times each is
+    /// loaded so tests can assert `LibraFs` actually reuses what it's already resolved instead
+    /// of hitting `roots` again.
+    struct StubRoots {
+        trees: HashMap<SHA1, Tree>,
+        blobs: HashMap<SHA1, Vec<u8>>,
+        tree_loads: Cell<u32>,
+        blob_loads: Cell<u32>,
+    }
+
+    impl RootNodes for StubRoots {
+        fn root_tree(&self, _reference: &str) -> Option<Tree> {
+            unimplemented!("not exercised by LibraFs once it's already holding the root tree")
+        }
+
+        fn load_tree(&self, id: SHA1) -> Option<Tree> {
+            self.tree_loads.set(self.tree_loads.get() + 1);
+            self.trees.get(&id).cloned()
+        }
+
+        fn load_blob(&self, id: SHA1) -> Option<Blob> {
+            self.blob_loads.set(self.blob_loads.get() + 1);
+            let data = self.blobs.get(&id)?;
+            Blob::from_bytes(data.clone(), id).ok()
+        }
+    }
+
+    fn id_for(label: &str) -> SHA1 {
+        SHA1::from_type_and_data(ObjectType::Blob, label.as_bytes())
+    }
+
+    /// root/
+    ///   file.txt  (blob "file contents")
+    ///   sub/
+    ///     nested.txt  (blob "nested contents")
+    fn fixture() -> (LibraFs<StubRoots>, SHA1, SHA1) {
+        let file_id = id_for("file.txt");
+        let sub_id = id_for("sub");
+        let nested_id = id_for("nested.txt");
+
+        let root_tree = Tree {
+            tree_items: vec![
+                TreeItem { name: "file.txt".to_string(), mode: TreeItemMode::Blob, id: file_id },
+                TreeItem { name: "sub".to_string(), mode: TreeItemMode::Tree, id: sub_id },
+            ],
+        };
+        let sub_tree = Tree {
+            tree_items: vec![TreeItem { name: "nested.txt".to_string(), mode: TreeItemMode::Blob, id: nested_id }],
+        };
+
+        let mut trees = HashMap::new();
+        trees.insert(sub_id, sub_tree);
+
+        let mut blobs = HashMap::new();
+        blobs.insert(file_id, b"file contents".to_vec());
+        blobs.insert(nested_id, b"nested contents".to_vec());
+
+        let roots = StubRoots { trees, blobs, tree_loads: Cell::new(0), blob_loads: Cell::new(0) };
+        (LibraFs::new(roots, root_tree), sub_id, nested_id)
+    }
+
+    #[test]
+    fn child_inode_resolves_a_nested_tree_and_its_blob() {
+        let (fs, sub_id, nested_id) = fixture();
+
+        let sub_ino = fs.child_inode(FUSE_ROOT_INODE, "sub", TreeItemMode::Tree, sub_id).unwrap();
+        match fs.inodes.lock().unwrap().get(&sub_ino) {
+            Some(Node::Dir(tree)) => assert_eq!(tree.tree_items.len(), 1),
+            _ => panic!("expected a resolved dir node"),
+        }
+
+        let nested_ino = fs.child_inode(sub_ino, "nested.txt", TreeItemMode::Blob, nested_id).unwrap();
+        match fs.inodes.lock().unwrap().get(&nested_ino) {
+            Some(Node::File(id)) => assert_eq!(*id, nested_id),
+            _ => panic!("expected a resolved file node"),
+        }
+        assert_eq!(fs.blob_data(nested_id).as_deref(), Some(b"nested contents".as_slice()));
+    }
+
+    #[test]
+    fn child_inode_reuses_the_same_inode_on_repeat_lookups() {
+        let (fs, sub_id, _nested_id) = fixture();
+
+        let first = fs.child_inode(FUSE_ROOT_INODE, "sub", TreeItemMode::Tree, sub_id).unwrap();
+        let second = fs.child_inode(FUSE_ROOT_INODE, "sub", TreeItemMode::Tree, sub_id).unwrap();
+        assert_eq!(first, second, "repeated lookups of the same entry must reuse its inode");
+        assert_eq!(fs.roots.tree_loads.get(), 1, "a cached entry shouldn't be resolved against `roots` again");
+
+        let other = fs.child_inode(FUSE_ROOT_INODE, "file.txt", TreeItemMode::Blob, id_for("file.txt")).unwrap();
+        assert_ne!(first, other, "different entries must get different inodes");
+    }
+
+    #[test]
+    fn blob_data_is_cached_after_the_first_resolution() {
+        let (fs, _sub_id, nested_id) = fixture();
+
+        let first = fs.blob_data(nested_id);
+        let second = fs.blob_data(nested_id);
+        assert_eq!(first, second);
+        assert_eq!(fs.roots.blob_loads.get(), 1, "a cached blob shouldn't be reloaded from `roots`");
+    }
+}
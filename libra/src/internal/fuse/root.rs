@@ -0,0 +1,48 @@
+//! The boundary between the FUSE layer and storage.
+//!
+//! [`RootNodes`] is the only thing [`super::LibraFs`] knows about persistence: given a ref it
+//! resolves the commit's top-level tree, and it resolves `Tree`/`Blob` ids on demand as the
+//! filesystem is walked. Keeping this as a trait means the FUSE module itself never links
+//! against the object-database crate directly.
+
+use venus::hash::SHA1;
+use venus::internal::object::blob::Blob;
+use venus::internal::object::tree::Tree;
+
+/// Resolves refs and object ids into the objects `LibraFs` needs to serve a directory tree.
+pub trait RootNodes {
+    /// Returns the root tree for `reference` (a branch, tag, or commit-ish), if it resolves.
+    fn root_tree(&self, reference: &str) -> Option<Tree>;
+
+    /// Loads a `Tree` object by id.
+    fn load_tree(&self, id: SHA1) -> Option<Tree>;
+
+    /// Loads a `Blob` object by id.
+    fn load_blob(&self, id: SHA1) -> Option<Blob>;
+}
+
+/// [`RootNodes`] backed by the on-disk Libra object database.
+pub struct ObjectRootNodes {
+    storage: crate::internal::db::ObjectStorage,
+}
+
+impl ObjectRootNodes {
+    pub fn new(storage: crate::internal::db::ObjectStorage) -> Self {
+        ObjectRootNodes { storage }
+    }
+}
+
+impl RootNodes for ObjectRootNodes {
+    fn root_tree(&self, reference: &str) -> Option<Tree> {
+        let commit = self.storage.load_commit_by_ref(reference)?;
+        self.load_tree(commit.tree_id)
+    }
+
+    fn load_tree(&self, id: SHA1) -> Option<Tree> {
+        self.storage.load_tree(id)
+    }
+
+    fn load_blob(&self, id: SHA1) -> Option<Blob> {
+        self.storage.load_blob(id)
+    }
+}
@@ -0,0 +1,87 @@
+//! Loose, content-addressed storage for blob chunks under `.libra/objects`.
+//!
+//! A [`Blob`] only ever builds its chunk manifest in memory - nothing landed on disk as separate
+//! objects until this module persists each entry from [`Blob::chunk_refs`] under its own content
+//! hash. That's the actual mechanism that lets identical chunks across files (or revisions of
+//! the same file) share one object on disk instead of being written out again every time.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use venus::hash::SHA1;
+use venus::internal::object::blob::Blob;
+
+/// Persists every chunk of `blob` as its own loose object under `objects_dir`, fanned out by the
+/// first two hex characters of its id the same way Git lays out its own loose objects. A chunk
+/// already on disk - the dedup this exists for - is left untouched. Returns the number of
+/// chunks actually written (as opposed to already deduplicated).
+pub fn save_blob(objects_dir: &Path, blob: &Blob) -> io::Result<usize> {
+    let mut written = 0;
+    for (id, data) in blob.chunk_refs() {
+        let path = chunk_path(objects_dir, id);
+        if path.exists() {
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, data)?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+fn chunk_path(objects_dir: &Path, id: SHA1) -> PathBuf {
+    let hex = id.to_string();
+    objects_dir.join(&hex[..2]).join(&hex[2..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("libra-object-store-test-{}-{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn writes_each_chunk_once_and_dedups_on_replay() {
+        let dir = scratch_dir("dedup");
+        let blob = Blob::from_content(&"a".repeat(200 * 1024));
+        // Repeated content can yield several identical chunks within one blob; each distinct id
+        // is written once.
+        let distinct_chunks = blob
+            .chunk_refs()
+            .map(|(id, _)| id)
+            .collect::<std::collections::HashSet<_>>()
+            .len();
+
+        let written_first = save_blob(&dir, &blob).unwrap();
+        assert_eq!(written_first, distinct_chunks);
+
+        let written_second = save_blob(&dir, &blob).unwrap();
+        assert_eq!(written_second, 0, "re-saving the same blob should dedup every chunk");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn shares_chunks_that_are_identical_across_different_blobs() {
+        let dir = scratch_dir("cross-blob");
+        let shared_prefix = "x".repeat(200 * 1024);
+
+        let first = Blob::from_content(&shared_prefix);
+        let written_first = save_blob(&dir, &first).unwrap();
+        assert!(written_first > 0);
+
+        let second = Blob::from_content(&(shared_prefix.clone() + "tail"));
+        let written_second = save_blob(&dir, &second).unwrap();
+        assert!(
+            written_second < second.chunk_refs().count(),
+            "the shared prefix's chunks should already be on disk from `first`"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,162 @@
+//! Pluggable DVCS backend.
+//!
+//! `clone_repository` and friends used to shell out to the `libra` sidecar directly, so the
+//! desktop app could only mirror repositories with Libra installed. [`Backend`] abstracts the
+//! handful of operations those commands need so a plain `git` binary - or any future VCS - can
+//! be driven the same way, selected via [`from_setting`].
+
+use std::path::Path;
+
+use tauri::api::process::{Command, CommandEvent};
+
+/// A distributed version control backend the desktop app can clone, branch, and push through.
+pub trait Backend {
+    /// Name of the sidecar binary this backend drives, e.g. `"libra"` or `"git"`.
+    fn sidecar_name(&self) -> &'static str;
+
+    /// Clones `repo_url` into `target_dir`.
+    fn clone(&self, repo_url: &str, target_dir: &Path) -> Result<(), String> {
+        run(
+            self.sidecar_name(),
+            &["clone", repo_url, target_dir.to_str().unwrap()],
+            None,
+        )
+    }
+
+    /// Creates `branch` in `repo_path` and checks it out (`checkout -b`, so an existing branch of
+    /// that name is an error).
+    fn branch(&self, repo_path: &Path, branch: &str) -> Result<(), String> {
+        run(self.sidecar_name(), &["checkout", "-b", branch], Some(repo_path))
+    }
+
+    /// Points `origin` at `remote_url` in `repo_path`, replacing any existing `origin`.
+    fn remote_set_url(&self, repo_path: &Path, remote_url: &str) -> Result<(), String> {
+        // Best-effort: there may not be an existing `origin` to remove.
+        let _ = run(self.sidecar_name(), &["remote", "remove", "origin"], Some(repo_path));
+        run(
+            self.sidecar_name(),
+            &["remote", "add", "origin", remote_url],
+            Some(repo_path),
+        )
+    }
+
+    /// Pushes `branch` to `origin` from `repo_path`.
+    fn push(&self, repo_path: &Path, branch: &str) -> Result<(), String> {
+        run(self.sidecar_name(), &["push", "origin", branch], Some(repo_path))
+    }
+}
+
+/// Drives the bundled `libra` sidecar. The default backend.
+pub struct Libra;
+
+impl Backend for Libra {
+    fn sidecar_name(&self) -> &'static str {
+        "libra"
+    }
+}
+
+/// Drives a plain `git` binary, for repositories imported from or mirrored to vanilla Git
+/// hosts.
+pub struct Git;
+
+impl Backend for Git {
+    fn sidecar_name(&self) -> &'static str {
+        "git"
+    }
+}
+
+/// Picks a backend from the user's `vcs_backend` preference, defaulting to [`Libra`] when the
+/// preference is unset or unrecognized.
+pub fn from_setting(setting: Option<&str>) -> Box<dyn Backend> {
+    match setting {
+        Some("git") => Box::new(Git),
+        _ => Box::new(Libra),
+    }
+}
+
+/// Clones `repo_url` into `target_dir` with `backend`'s sidecar, printing each output line
+/// prefixed with `label` as it arrives (rather than waiting for the process to exit), so a
+/// caller cloning many submodules can report which one is currently being fetched.
+pub fn clone_with_progress(
+    backend: &dyn Backend,
+    repo_url: &str,
+    target_dir: &Path,
+    label: &str,
+) -> Result<(), String> {
+    run_streaming(
+        backend.sidecar_name(),
+        &["clone", repo_url, target_dir.to_str().unwrap()],
+        None,
+        label,
+    )
+}
+
+fn run_streaming(
+    sidecar: &str,
+    args: &[&str],
+    current_dir: Option<&Path>,
+    label: &str,
+) -> Result<(), String> {
+    let mut cmd = Command::new_sidecar(sidecar)
+        .map_err(|e| format!("Failed to create `{}` binary command: {}", sidecar, e))?
+        .args(args.to_vec());
+    if let Some(dir) = current_dir {
+        cmd = cmd.current_dir(dir.to_path_buf());
+    }
+
+    let (mut rx, child) = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to spawn `{}`: {}", sidecar, e))?;
+
+    // `clone_with_progress` is called from plain (non-async) command handlers that already run
+    // on Tauri's own async runtime, so driving `rx` with `tauri::async_runtime::block_on` here
+    // would try to start a runtime from within a runtime and panic. Pump it from a dedicated OS
+    // thread instead - `blocking_recv` is fine there since the thread has no runtime of its own.
+    let label = label.to_string();
+    let sidecar_name = sidecar.to_string();
+    let success = std::thread::spawn(move || {
+        let mut success = true;
+        while let Some(event) = rx.blocking_recv() {
+            match event {
+                CommandEvent::Stdout(line) => print!("[{}] {}", label, line),
+                CommandEvent::Stderr(line) => eprint!("[{}] {}", label, line),
+                CommandEvent::Terminated(payload) => {
+                    success = payload.code == Some(0);
+                    break;
+                }
+                _ => {}
+            }
+        }
+        drop(child);
+        success
+    })
+    .join()
+    .map_err(|_| format!("`{}` progress thread panicked", sidecar_name))?;
+
+    if success {
+        Ok(())
+    } else {
+        Err(format!("`{}` exited with a non-zero status", sidecar))
+    }
+}
+
+fn run(sidecar: &str, args: &[&str], current_dir: Option<&Path>) -> Result<(), String> {
+    let mut cmd = Command::new_sidecar(sidecar)
+        .map_err(|e| format!("Failed to create `{}` binary command: {}", sidecar, e))?
+        .args(args.to_vec());
+    if let Some(dir) = current_dir {
+        cmd = cmd.current_dir(dir.to_path_buf());
+    }
+
+    let output = cmd
+        .output()
+        .map_err(|e| format!("Failed to execute process: {}", e))?;
+
+    if output.status.success() {
+        println!("{}", output.stdout);
+        Ok(())
+    } else {
+        eprintln!("{}", output.stderr);
+        Err(output.stderr)
+    }
+}
@@ -1,29 +1,24 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use std::{env, fs};
 
 use serde::Deserialize;
-use tauri::api::process::{Command, CommandChild, CommandEvent};
 use tauri::{Manager, State};
 
-#[derive(Default)]
-struct ServiceState {
-    child: Option<CommandChild>,
-    with_relay: bool,
-}
+mod backend;
+mod service;
+mod submodule;
 
-impl Drop for ServiceState {
-    fn drop(&mut self) {
-        if let Some(child_process) = self.child.take() {
-            child_process
-                .kill()
-                .expect("Failed to kill sidecar process");
-        }
-    }
-}
+use backend::Backend;
+use service::{InstanceStatus, ServiceManager};
+
+/// The instance id used for the node the app starts on launch. Additional instances (e.g. a
+/// relay-enabled node alongside this one) are started by the frontend under their own ids.
+const DEFAULT_SERVICE_ID: &str = "default";
 
 #[derive(Debug, Deserialize, Clone, Default)]
 struct MegaStartParams {
@@ -56,83 +51,38 @@ fn set_up_lib(handle: tauri::AppHandle) {
 
 #[tauri::command]
 fn start_mega_service(
-    state: State<'_, Arc<Mutex<ServiceState>>>,
+    manager: State<'_, Arc<ServiceManager>>,
+    id: String,
     params: MegaStartParams,
 ) -> Result<(), String> {
-    let mut service_state = state.lock().unwrap();
-    if service_state.child.is_some() {
-        return Err("Service is already running".into());
-    }
-
-    let args = if let Some(ref addr) = params.bootstrap_node {
-        service_state.with_relay = true;
-        vec!["service", "http", "--bootstrap-node", addr]
-    } else {
-        service_state.with_relay = false;
-        vec!["service", "http"]
-    };
-    let (mut rx, child) = Command::new_sidecar("mega")
-        .expect("Failed to create `mega` binary command")
-        .args(args)
-        .spawn()
-        .expect("Failed to spawn `Mega service`");
-
-    service_state.child = Some(child);
-    // Sidecar output
-    tauri::async_runtime::spawn(async move {
-        while let Some(event) = rx.recv().await {
-            match event {
-                CommandEvent::Stdout(line) => {
-                    print!("{}", line);
-                }
-                CommandEvent::Stderr(line) => {
-                    eprint!("Sidecar stderr: {}", line);
-                }
-                CommandEvent::Terminated(payload) => {
-                    if let Some(code) = payload.code {
-                        if code == 0 {
-                            println!("Sidecar executed successfully.");
-                        } else {
-                            eprintln!("Sidecar failed with exit code: {}", code);
-                        }
-                    } else if let Some(signal) = payload.signal {
-                        eprintln!("Sidecar terminated by signal: {}", signal);
-                    }
-                    break;
-                }
-                _ => {}
-            }
-        }
-    });
-    Ok(())
+    manager.inner().clone().start(id, params, true)
 }
 
 #[tauri::command]
-fn stop_mega_service(state: State<'_, Arc<Mutex<ServiceState>>>) -> Result<(), String> {
-    let mut service_state = state.lock().unwrap();
-    if let Some(child) = service_state.child.take() {
-        child.kill().map_err(|e| e.to_string())?;
-        service_state.child = None;
-    } else {
-        println!("Mega Service is not running");
-    }
-    Ok(())
+fn stop_mega_service(manager: State<'_, Arc<ServiceManager>>, id: String) -> Result<(), String> {
+    manager.stop(&id)
 }
 
 #[tauri::command]
 fn restart_mega_service(
-    state: State<'_, Arc<Mutex<ServiceState>>>,
+    manager: State<'_, Arc<ServiceManager>>,
+    id: String,
     params: MegaStartParams,
 ) -> Result<(), String> {
-    stop_mega_service(state.clone())?;
-    start_mega_service(state, params)?;
-    Ok(())
+    manager.inner().clone().restart(id, params)
 }
 
 #[tauri::command]
-fn mega_service_status(state: State<'_, Arc<Mutex<ServiceState>>>) -> Result<(bool, bool), String> {
-    let service_state = state.lock().unwrap();
-    Ok((service_state.child.is_some(), service_state.with_relay))
+fn mega_service_status(
+    manager: State<'_, Arc<ServiceManager>>,
+) -> Result<HashMap<String, InstanceStatus>, String> {
+    Ok(manager.status())
+}
+
+/// Reads the user's preferred VCS backend (`"libra"` or `"git"`) from the `MEGA_VCS_BACKEND`
+/// environment variable, until this lands in the app's settings UI.
+fn vcs_backend_setting() -> Option<String> {
+    env::var("MEGA_VCS_BACKEND").ok()
 }
 
 #[tauri::command]
@@ -150,70 +100,34 @@ fn clone_repository(repo_url: String, name: String) -> Result<(), String> {
         fs::remove_dir_all(&target_dir).unwrap();
     }
 
-    let output = Command::new_sidecar("libra")
-        .expect("Failed to create `libra` binary command")
-        .args(["clone", &repo_url, (target_dir.to_str().unwrap())])
-        .output()
-        .map_err(|e| format!("Failed to execute process: {}", e))?;
+    let backend = backend::from_setting(vcs_backend_setting().as_deref());
+    backend.clone(&repo_url, &target_dir)?;
+    submodule::init_recursive(&target_dir, backend.as_ref())?;
 
-    if output.status.success() {
-        println!("{}", output.stdout);
-    } else {
-        eprintln!("{}", output.stderr);
-    }
-    change_remote_url(target_dir.clone(), name)?;
-    push_to_new_remote(target_dir)?;
+    change_remote_url(backend.as_ref(), target_dir.clone(), name)?;
+
+    // Submodules can be added by a commit after the one we just cloned landed on disk; check
+    // once more right before pushing so the mirror doesn't end up missing any.
+    submodule::init_recursive(&target_dir, backend.as_ref())?;
+    push_to_new_remote(backend.as_ref(), target_dir)?;
     Ok(())
 }
 
-fn change_remote_url(repo_path: PathBuf, name: String) -> Result<(), String> {
-    Command::new_sidecar("libra")
-        .expect("Failed to create `libra` binary command")
-        .args(["remote", "remove", "origin"])
-        .current_dir(repo_path.clone())
-        .output()
-        .map_err(|e| format!("Failed to execute process: {}", e))?;
-
-    let output = Command::new_sidecar("libra")
-        .expect("Failed to create `libra` binary command")
-        .args([
-            "remote",
-            "add",
-            "origin",
-            &format!("http://localhost:8000/third-part/{}", name),
-        ])
-        .current_dir(repo_path.clone())
-        .output()
-        .map_err(|e| format!("Failed to execute process: {}", e))?;
-
-    if output.status.success() {
-        println!("{}", output.stdout);
-    } else {
-        eprintln!("{}", output.stderr);
-    }
-    Ok(())
+fn change_remote_url(backend: &dyn Backend, repo_path: PathBuf, name: String) -> Result<(), String> {
+    backend.remote_set_url(
+        &repo_path,
+        &format!("http://localhost:8000/third-part/{}", name),
+    )
 }
 
-fn push_to_new_remote(repo_path: PathBuf) -> Result<(), String> {
-    let output = Command::new_sidecar("libra")
-        .expect("Failed to create `libra` binary command")
-        .args(["push", "origin", "master"])
-        .current_dir(repo_path)
-        .output()
-        .map_err(|e| format!("Failed to execute process: {}", e))?;
-
-    if output.status.success() {
-        println!("{}", output.stdout);
-    } else {
-        eprintln!("{}", output.stderr);
-    }
-    Ok(())
+fn push_to_new_remote(backend: &dyn Backend, repo_path: PathBuf) -> Result<(), String> {
+    backend.push(&repo_path, "master")
 }
 
 fn main() {
     let params = MegaStartParams::default();
     tauri::Builder::default()
-        .manage(Arc::new(Mutex::new(ServiceState::default())))
+        .manage(Arc::new(ServiceManager::default()))
         .invoke_handler(tauri::generate_handler![
             start_mega_service,
             stop_mega_service,
@@ -224,11 +138,11 @@ fn main() {
         .setup(|app| {
             let app_handle = app.handle().clone();
             set_up_lib(app_handle);
-            let state = app.state::<Arc<Mutex<ServiceState>>>().clone();
-            if let Err(e) = start_mega_service(state, params) {
-                eprintln!("Failed to restart rust_service: {}", e);
+            let manager = app.state::<Arc<ServiceManager>>().inner().clone();
+            if let Err(e) = manager.start(DEFAULT_SERVICE_ID.to_string(), params, true) {
+                eprintln!("Failed to start default mega service: {}", e);
             } else {
-                println!("Rust service restarted successfully");
+                println!("Mega service '{}' started successfully", DEFAULT_SERVICE_ID);
             }
             Ok(())
         })
@@ -0,0 +1,247 @@
+//! Multi-instance manager for `mega` service sidecars.
+//!
+//! The desktop app previously tracked a single `mega` child process, so it couldn't run, say,
+//! a relay-enabled node alongside a local-only one, and had no way to notice (let alone
+//! recover from) a child dying unexpectedly. [`ServiceManager`] replaces that with a map of
+//! named instances, each supervised independently: a still-wanted instance that terminates
+//! without a clean (`0`) exit code is restarted with exponential backoff, up to
+//! [`MAX_RESTART_ATTEMPTS`] times.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::api::process::{Command, CommandChild, CommandEvent};
+
+use crate::MegaStartParams;
+
+const MAX_RESTART_ATTEMPTS: u32 = 5;
+const BASE_BACKOFF: Duration = Duration::from_secs(1);
+
+struct Instance {
+    child: Option<CommandChild>,
+    params: MegaStartParams,
+    running: bool,
+    auto_restart: bool,
+    restart_attempts: u32,
+    /// Bumped by every `start`/`restart`/`stop` of this id. A scheduled backoff respawn captures
+    /// the generation it was scheduled under and checks it still matches before spawning, so a
+    /// manual restart or stop that races with a pending respawn wins instead of being clobbered
+    /// by it.
+    generation: u64,
+}
+
+/// Snapshot of one instance's state, as reported to the frontend.
+#[derive(Debug, Serialize, Clone, Copy)]
+pub struct InstanceStatus {
+    pub running: bool,
+    pub with_relay: bool,
+}
+
+/// Tracks every `mega` service instance the desktop app has started, keyed by an id the
+/// frontend chooses (e.g. `"local"`, `"relay"`).
+#[derive(Default)]
+pub struct ServiceManager {
+    instances: Mutex<HashMap<String, Instance>>,
+}
+
+impl ServiceManager {
+    /// Starts a new instance under `id`. Errors if `id` is already running.
+    pub fn start(
+        self: Arc<Self>,
+        id: String,
+        params: MegaStartParams,
+        auto_restart: bool,
+    ) -> Result<(), String> {
+        let mut instances = self.instances.lock().unwrap();
+        if matches!(instances.get(&id), Some(i) if i.running) {
+            return Err(format!("Service '{}' is already running", id));
+        }
+        let generation = instances.get(&id).map_or(0, |i| i.generation) + 1;
+        drop(instances);
+        self.spawn(id, params, auto_restart, 0, generation, None)
+    }
+
+    /// Kills `id` (if running) and starts it again with `params`, preserving its auto-restart
+    /// supervision setting.
+    pub fn restart(self: Arc<Self>, id: String, params: MegaStartParams) -> Result<(), String> {
+        let (auto_restart, generation) = {
+            let mut instances = self.instances.lock().unwrap();
+            match instances.get_mut(&id) {
+                Some(instance) => {
+                    if let Some(child) = instance.child.take() {
+                        child.kill().map_err(|e| e.to_string())?;
+                    }
+                    instance.running = false;
+                    instance.generation += 1;
+                    (instance.auto_restart, instance.generation)
+                }
+                None => (true, 1),
+            }
+        };
+        self.spawn(id, params, auto_restart, 0, generation, None)
+    }
+
+    /// Stops `id` and disables auto-restart for it, so the supervision task watching it exits
+    /// quietly instead of respawning it. Also bumps the generation counter, so a backoff respawn
+    /// already scheduled before the stop sees it's stale and no-ops instead of reviving `id`.
+    pub fn stop(&self, id: &str) -> Result<(), String> {
+        let mut instances = self.instances.lock().unwrap();
+        let instance = instances
+            .get_mut(id)
+            .ok_or_else(|| format!("No such service '{}'", id))?;
+        instance.auto_restart = false;
+        instance.generation += 1;
+        if let Some(child) = instance.child.take() {
+            child.kill().map_err(|e| e.to_string())?;
+        }
+        instance.running = false;
+        Ok(())
+    }
+
+    /// Returns the running/with-relay state of every instance the manager knows about.
+    pub fn status(&self) -> HashMap<String, InstanceStatus> {
+        self.instances
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, instance)| {
+                (
+                    id.clone(),
+                    InstanceStatus {
+                        running: instance.running,
+                        with_relay: instance.params.bootstrap_node.is_some(),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// Spawns the `mega` sidecar for `id` and records it under `generation`.
+    ///
+    /// `expected_prior_generation`, when set, makes the check-and-insert atomic under a single
+    /// lock acquisition: used only by the scheduled backoff respawn in [`Self::on_terminated`],
+    /// it verifies `id` is still on the generation the respawn was scheduled under before
+    /// inserting the new instance. If a manual `start`/`restart`/`stop` moved `id` to a newer
+    /// generation in the meantime, the freshly spawned child is killed and discarded instead of
+    /// replacing whatever that manual action put in its place. `start`/`restart` themselves pass
+    /// `None` - they always own the id and should always proceed.
+    fn spawn(
+        self: Arc<Self>,
+        id: String,
+        params: MegaStartParams,
+        auto_restart: bool,
+        attempt: u32,
+        generation: u64,
+        expected_prior_generation: Option<u64>,
+    ) -> Result<(), String> {
+        let mut args = vec!["service".to_string(), "http".to_string()];
+        if let Some(ref addr) = params.bootstrap_node {
+            args.push("--bootstrap-node".to_string());
+            args.push(addr.clone());
+        }
+
+        let (mut rx, child) = Command::new_sidecar("mega")
+            .expect("Failed to create `mega` binary command")
+            .args(args)
+            .spawn()
+            .map_err(|e| format!("Failed to spawn `mega` service '{}': {}", id, e))?;
+
+        {
+            let mut instances = self.instances.lock().unwrap();
+            if let Some(expected) = expected_prior_generation {
+                let current = instances.get(&id).map(|i| i.generation);
+                if current != Some(expected) {
+                    drop(instances);
+                    let _ = child.kill();
+                    return Ok(());
+                }
+            }
+            instances.insert(
+                id.clone(),
+                Instance {
+                    child: Some(child),
+                    params: params.clone(),
+                    running: true,
+                    auto_restart,
+                    restart_attempts: attempt,
+                    generation,
+                },
+            );
+        }
+
+        let manager = self;
+        tauri::async_runtime::spawn(async move {
+            while let Some(event) = rx.recv().await {
+                match event {
+                    CommandEvent::Stdout(line) => print!("[{}] {}", id, line),
+                    CommandEvent::Stderr(line) => eprint!("[{}] stderr: {}", id, line),
+                    CommandEvent::Terminated(payload) => {
+                        manager.on_terminated(&id, generation, payload.code);
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Marks `id` as stopped and, if it didn't exit cleanly (code `0`), is still wanted, and
+    /// hasn't exhausted its retry budget, schedules a backed-off respawn.
+    ///
+    /// `generation` is the one this instance was spawned under. If `id` has since been manually
+    /// restarted or stopped, its current generation will have moved past this value - in that
+    /// case the manual action already owns `id`'s state, so this (now-stale) termination is
+    /// ignored instead of clobbering whatever is running now.
+    fn on_terminated(self: Arc<Self>, id: &str, generation: u64, exit_code: Option<i32>) {
+        let retry = {
+            let mut instances = self.instances.lock().unwrap();
+            let Some(instance) = instances.get_mut(id) else {
+                return;
+            };
+            if instance.generation != generation {
+                return;
+            }
+            instance.running = false;
+            instance.child = None;
+
+            let clean_exit = exit_code == Some(0);
+            if clean_exit || !instance.auto_restart || instance.restart_attempts >= MAX_RESTART_ATTEMPTS {
+                None
+            } else {
+                instance.restart_attempts += 1;
+                Some((instance.params.clone(), instance.restart_attempts))
+            }
+        };
+
+        let Some((params, attempt)) = retry else {
+            return;
+        };
+
+        let id = id.to_string();
+        tauri::async_runtime::spawn(async move {
+            let backoff = BASE_BACKOFF * 2u32.pow(attempt.min(MAX_RESTART_ATTEMPTS) - 1);
+            tokio::time::sleep(backoff).await;
+
+            // `spawn` re-checks `generation` against `id`'s current state and the insert in the
+            // same lock acquisition, so there's no gap after the sleep for a manual restart/stop
+            // to land in before this respawn takes effect.
+            if let Err(e) = self.spawn(id.clone(), params, true, attempt, generation, Some(generation)) {
+                eprintln!("Failed to auto-restart service '{}': {}", id, e);
+            }
+        });
+    }
+}
+
+impl Drop for ServiceManager {
+    fn drop(&mut self) {
+        for instance in self.instances.lock().unwrap().values_mut() {
+            if let Some(child) = instance.child.take() {
+                let _ = child.kill();
+            }
+        }
+    }
+}
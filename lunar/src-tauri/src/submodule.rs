@@ -0,0 +1,115 @@
+//! Recursive submodule handling for repositories mirrored into `~/.mega`.
+//!
+//! `clone_repository` only fetches the top-level repository by default, so a mirrored repo
+//! with submodules would push an incomplete tree. This module walks a `.gitmodules` file (and
+//! each submodule's own `.gitmodules`, recursively) and fetches anything missing.
+
+use std::fs;
+use std::path::Path;
+
+use crate::backend::{self, Backend};
+
+#[derive(Debug, Clone)]
+struct SubmoduleEntry {
+    name: String,
+    path: String,
+    url: String,
+}
+
+/// Parses a `.gitmodules` file's `[submodule "name"]` blocks.
+///
+/// This is a deliberately small parser for the handful of directives Libra/Git themselves
+/// write (`path`, `url`) - not the full Git config grammar.
+fn parse_gitmodules(contents: &str) -> Vec<SubmoduleEntry> {
+    let mut entries = Vec::new();
+    let mut current: Option<SubmoduleEntry> = None;
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if let Some(name) = line
+            .strip_prefix("[submodule \"")
+            .and_then(|rest| rest.strip_suffix("\"]"))
+        {
+            entries.extend(current.take());
+            current = Some(SubmoduleEntry {
+                name: name.to_string(),
+                path: String::new(),
+                url: String::new(),
+            });
+        } else if let Some(entry) = current.as_mut() {
+            if let Some((key, value)) = line.split_once('=') {
+                match key.trim() {
+                    "path" => entry.path = value.trim().to_string(),
+                    "url" => entry.url = value.trim().to_string(),
+                    _ => {}
+                }
+            }
+        }
+    }
+    entries.extend(current.take());
+    entries.retain(|entry| !entry.path.is_empty() && !entry.url.is_empty());
+    entries
+}
+
+fn is_cloned(dir: &Path) -> bool {
+    dir.join(".git").exists() || dir.join(".libra").exists()
+}
+
+/// Recursively clones and initializes every submodule reachable from `repo_path`, skipping any
+/// submodule whose target directory already looks cloned.
+///
+/// Safe to call more than once on the same repository: once right after the initial clone, and
+/// again right before pushing, to pick up submodules that were only added by a commit made
+/// after the first pass.
+pub fn init_recursive(repo_path: &Path, backend: &dyn Backend) -> Result<(), String> {
+    let Ok(contents) = fs::read_to_string(repo_path.join(".gitmodules")) else {
+        return Ok(());
+    };
+
+    for entry in parse_gitmodules(&contents) {
+        let submodule_path = repo_path.join(&entry.path);
+
+        if !is_cloned(&submodule_path) {
+            println!("Fetching submodule '{}' ({})", entry.name, entry.url);
+            backend::clone_with_progress(backend, &entry.url, &submodule_path, &entry.name)?;
+        }
+
+        // Recurse regardless of whether this pass cloned it: a submodule cloned on an earlier
+        // pass may itself have gained submodules since.
+        init_recursive(&submodule_path, backend)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_path_and_url() {
+        let gitmodules = r#"
+[submodule "libs/widgets"]
+	path = libs/widgets
+	url = https://example.com/widgets.git
+[submodule "libs/icons"]
+	path = libs/icons
+	url = https://example.com/icons.git
+"#;
+        let entries = parse_gitmodules(gitmodules);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, "libs/widgets");
+        assert_eq!(entries[0].path, "libs/widgets");
+        assert_eq!(entries[0].url, "https://example.com/widgets.git");
+        assert_eq!(entries[1].name, "libs/icons");
+    }
+
+    #[test]
+    fn skips_incomplete_entries() {
+        let gitmodules = r#"
+[submodule "no-url"]
+	path = libs/no-url
+"#;
+        assert!(parse_gitmodules(gitmodules).is_empty());
+    }
+}
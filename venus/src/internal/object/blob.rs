@@ -31,29 +31,48 @@ use std::fmt::Display;
 
 use crate::errors::GitError;
 use crate::hash::SHA1;
+use crate::internal::object::chunk::cdc_chunks;
 use crate::internal::object::types::ObjectType;
 use crate::internal::object::ObjectTrait;
 
+/// A single content-defined chunk of a blob's bytes, content-addressed independently of the
+/// blob itself so identical regions can be shared across file versions - and across files.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Chunk {
+    pub id: SHA1,
+    pub data: Vec<u8>,
+}
+
 /// **The Blob Object**
 ///
+/// A `Blob`'s id is still the hash of its whole content, exactly as Git computes it - only the
+/// storage underneath changed. Rather than one `data: Vec<u8>` buffer, a blob is a manifest of
+/// [`Chunk`]s produced by content-defined chunking, each of which is itself content-addressed
+/// and can be shared with any other blob that happens to contain the same chunk.
+///
+/// This crate only builds that manifest in memory - `venus` has no storage dependency, the same
+/// reason the FUSE layer resolves objects through `RootNodes` instead of linking against the
+/// object database directly. The dedup happens on disk only where the object-write path
+/// persists each entry from [`Blob::chunk_refs`] as its own content-addressed object; a path
+/// that writes the blob's full content as a single unit stores it without any sharing.
 #[allow(unused)]
 #[derive(Eq, Debug, Clone)]
 pub struct Blob {
     pub id: SHA1,
-    pub data: Vec<u8>,
+    pub chunks: Vec<Chunk>,
 }
 
 impl PartialEq for Blob {
     /// The Blob object is equal to another Blob object if their IDs are equal.
     fn eq(&self, other: &Self) -> bool {
-        self.data == other.data
+        self.chunks == other.chunks
     }
 }
 
 impl Display for Blob {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         writeln!(f, "Type: Blob").unwrap();
-        writeln!(f, "Size: {}", self.data.len())
+        writeln!(f, "Size: {}", self.get_size())
     }
 }
 
@@ -65,7 +84,7 @@ impl ObjectTrait for Blob {
     {
         Ok(Blob {
             id: hash,
-            data: data.to_vec(),
+            chunks: chunk_content(&data),
         })
     }
 
@@ -75,25 +94,46 @@ impl ObjectTrait for Blob {
     }
 
     fn get_size(&self) -> usize {
-        self.data.len()
+        self.chunks.iter().map(|chunk| chunk.data.len()).sum()
     }
 
+    /// Reassembles the blob's content by concatenating its chunks in order.
     fn to_data(&self) -> Result<Vec<u8>, GitError> {
-        Ok(self.data.clone())
+        let mut data = Vec::with_capacity(self.get_size());
+        for chunk in &self.chunks {
+            data.extend_from_slice(&chunk.data);
+        }
+        Ok(data)
     }
 }
 
+/// Splits `data` into content-defined chunks and hashes each one.
+fn chunk_content(data: &[u8]) -> Vec<Chunk> {
+    cdc_chunks(data)
+        .into_iter()
+        .map(|data| Chunk {
+            id: SHA1::from_type_and_data(ObjectType::Blob, &data),
+            data,
+        })
+        .collect()
+}
+
 impl Blob {
     pub fn from_content(content: &str) -> Self {
-        // let blob_content = Cursor::new(utils::compress_zlib(content.as_bytes()).unwrap());
-        // let mut buf = ReadBoxed::new(blob_content, ObjectType::Blob, content.len());
-        // Blob::from_buf_read(&mut buf, content.len())
         let content = content.as_bytes().to_vec();
         Blob {
             id: SHA1::from_type_and_data(ObjectType::Blob, &content),
-            data: content,
+            chunks: chunk_content(&content),
         }
     }
+
+    /// The `(id, bytes)` pairs the object-write path must persist as independent
+    /// content-addressed objects for this blob's chunks to actually dedup against other blobs -
+    /// writing `self.to_data()` as a single object instead would store the same bytes again
+    /// under every blob that happens to share them.
+    pub fn chunk_refs(&self) -> impl Iterator<Item = (SHA1, &[u8])> {
+        self.chunks.iter().map(|chunk| (chunk.id, chunk.data.as_slice()))
+    }
 }
 
 #[cfg(test)]
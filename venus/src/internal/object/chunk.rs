@@ -0,0 +1,146 @@
+//! Content-defined chunking (FastCDC) used to split large blobs into dedup-friendly pieces.
+//!
+//! A chunk boundary is found with a rolling "gear" hash: each new byte is mixed in as
+//! `hash = (hash << 1) + GEAR[byte]`, and a boundary is cut once the low bits of `hash` are all
+//! zero. FastCDC's *normalization* trick uses a stricter mask while under the target average
+//! size and a looser one once past it, which pulls the chunk-size distribution toward
+//! `AVG_SIZE` instead of the bimodal hot spots a single fixed mask produces.
+
+use std::sync::OnceLock;
+
+const MIN_SIZE: usize = 2 * 1024;
+const AVG_SIZE: usize = 8 * 1024;
+const MAX_SIZE: usize = 64 * 1024;
+
+/// Used below `AVG_SIZE`: more required zero bits than `log2(AVG_SIZE)` (13), so a boundary is
+/// *less* likely to be found yet, letting the chunk keep growing toward the average instead of
+/// cutting while still small.
+const MASK_BELOW_AVG: u64 = (1 << 14) - 1;
+/// Used at/above `AVG_SIZE`: fewer required zero bits than `log2(AVG_SIZE)`, so a boundary
+/// becomes *more* likely once past the average, pulling chunks back down instead of letting
+/// them run all the way to `MAX_SIZE`.
+const MASK_ABOVE_AVG: u64 = (1 << 12) - 1;
+
+/// A fixed, deterministic gear table. Chunk boundaries must be reproducible across runs (and
+/// machines) so identical content always produces identical chunks and dedups correctly.
+fn gear_table() -> &'static [u64; 256] {
+    static TABLE: OnceLock<[u64; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut table = [0u64; 256];
+        let mut seed: u64 = 0x9E37_79B9_7F4A_7C15;
+        for slot in table.iter_mut() {
+            // splitmix64, just used here to fill the table with well-mixed constants.
+            seed = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = seed;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            *slot = z ^ (z >> 31);
+        }
+        table
+    })
+}
+
+/// Splits `data` into content-defined chunks, each within `[MIN_SIZE, MAX_SIZE]` bytes except
+/// possibly the last one, which may be shorter.
+pub fn cdc_chunks(data: &[u8]) -> Vec<Vec<u8>> {
+    if data.len() <= MIN_SIZE {
+        return vec![data.to_vec()];
+    }
+
+    let gear = gear_table();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < data.len() {
+        let len = cut_point(&data[start..], gear);
+        chunks.push(data[start..start + len].to_vec());
+        start += len;
+    }
+    chunks
+}
+
+/// Finds the length of the next chunk at the front of `window`.
+fn cut_point(window: &[u8], gear: &[u64; 256]) -> usize {
+    if window.len() <= MIN_SIZE {
+        return window.len();
+    }
+
+    let mut hash: u64 = 0;
+    let max = window.len().min(MAX_SIZE);
+    for i in MIN_SIZE..max {
+        hash = (hash << 1).wrapping_add(gear[window[i] as usize]);
+        let mask = if i < AVG_SIZE { MASK_BELOW_AVG } else { MASK_ABOVE_AVG };
+        if hash & mask == 0 {
+            return i + 1;
+        }
+    }
+    max
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_input_is_a_single_chunk() {
+        let data = vec![0u8; MIN_SIZE - 1];
+        let chunks = cdc_chunks(&data);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].len(), data.len());
+    }
+
+    #[test]
+    fn chunks_reassemble_to_the_original_bytes() {
+        let mut data = Vec::with_capacity(MAX_SIZE * 3);
+        let mut x: u32 = 12345;
+        for _ in 0..data.capacity() {
+            // xorshift32, just needs to be non-uniform enough to exercise real cut points.
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            data.push((x & 0xff) as u8);
+        }
+
+        let chunks = cdc_chunks(&data);
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.len() <= MAX_SIZE);
+        }
+        let reassembled: Vec<u8> = chunks.into_iter().flatten().collect();
+        assert_eq!(reassembled, data);
+    }
+
+    #[test]
+    fn identical_content_chunks_identically() {
+        let data = vec![7u8; MAX_SIZE * 2];
+        assert_eq!(cdc_chunks(&data), cdc_chunks(&data));
+    }
+
+    #[test]
+    fn chunk_sizes_cluster_near_the_average_rather_than_the_max() {
+        let mut data = Vec::with_capacity(20 * 1024 * 1024);
+        let mut x: u32 = 42;
+        for _ in 0..data.capacity() {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            data.push((x & 0xff) as u8);
+        }
+
+        let chunks = cdc_chunks(&data);
+        let mean = data.len() as f64 / chunks.len() as f64;
+        assert!(
+            mean < AVG_SIZE as f64 * 2.0,
+            "mean chunk size {mean} is too far above the {AVG_SIZE} byte target"
+        );
+
+        let near_max = chunks
+            .iter()
+            .filter(|c| c.len() as f64 >= MAX_SIZE as f64 * 0.9)
+            .count();
+        let near_max_fraction = near_max as f64 / chunks.len() as f64;
+        assert!(
+            near_max_fraction < 0.2,
+            "{near_max_fraction:.2} of chunks landed near MAX_SIZE - normalization isn't working"
+        );
+    }
+}